@@ -0,0 +1,110 @@
+#![deny(warnings)]
+
+/*
+ * json2toml
+ *
+ * Read tagged JSON from stdin, as produced by the toml-test framework,
+ * decode it and write the result to stdout as TOML.
+ *
+ * This is the inverse of toml-rs-decoder: together the two binaries
+ * implement both halves of the toml-test contract
+ * (https://github.com/toml-lang/toml-test), so this crate can be used
+ * with both `toml-test -decoder` and `toml-test -encoder`.
+ *
+ * Exit with non-zero status if the input cannot be decoded.
+ */
+
+use std::io;
+use std::io::prelude::*;
+
+use serde_json::Value as Json;
+use toml::Value as Toml;
+
+fn main() {
+    let mut input = String::new();
+    io::stdin().read_to_string(&mut input).unwrap();
+
+    let json: Json = match serde_json::from_str(&input) {
+        Ok(json) => json,
+        Err(error) => {
+            println!("failed to parse JSON: {}", error);
+            std::process::exit(1);
+        }
+    };
+
+    match convert(json) {
+        Ok(toml) => match toml::to_string(&toml) {
+            Ok(text) => print!("{}", text),
+            Err(error) => {
+                println!("failed to serialize TOML: {}", error);
+                std::process::exit(1);
+            }
+        },
+        Err(error) => {
+            println!("failed to convert tagged JSON to TOML: {}", error);
+            std::process::exit(1);
+        }
+    }
+}
+
+/// Convert a tagged JSON value (as produced by toml-rs-decoder) back
+/// into a `toml::Value`.
+///
+/// An object is a typed leaf value iff it has exactly the keys "type"
+/// and "value"; any other object is a table.
+fn convert(json: Json) -> Result<Toml, String> {
+    match json {
+        Json::Object(ref obj) if is_tagged_value(obj) => {
+            let tag = obj["type"].as_str().ok_or("\"type\" is not a string")?;
+            let value = obj["value"].as_str().ok_or("\"value\" is not a string")?;
+            convert_tagged(tag, value)
+        }
+        Json::Object(obj) => {
+            let table = obj
+                .into_iter()
+                .map(|(k, v)| convert(v).map(|toml| (k, toml)))
+                .collect::<Result<_, String>>()?;
+            Ok(Toml::Table(table))
+        }
+        Json::Array(arr) => {
+            let values = arr.into_iter().map(convert).collect::<Result<_, String>>()?;
+            Ok(Toml::Array(values))
+        }
+        _ => Err(format!("unexpected untagged JSON value: {}", json)),
+    }
+}
+
+fn is_tagged_value(obj: &serde_json::Map<String, Json>) -> bool {
+    obj.len() == 2 && obj.contains_key("type") && obj.contains_key("value")
+}
+
+fn convert_tagged(tag: &str, value: &str) -> Result<Toml, String> {
+    match tag {
+        "string" => Ok(Toml::String(value.to_string())),
+        "integer" => value
+            .parse::<i64>()
+            .map(Toml::Integer)
+            .map_err(|e| format!("invalid integer {:?}: {}", value, e)),
+        "float" => parse_float(value)
+            .map(Toml::Float)
+            .map_err(|e| format!("invalid float {:?}: {}", value, e)),
+        "bool" => value
+            .parse::<bool>()
+            .map(Toml::Boolean)
+            .map_err(|e| format!("invalid bool {:?}: {}", value, e)),
+        "datetime" | "datetime-local" | "date-local" | "time-local" => value
+            .parse::<toml::value::Datetime>()
+            .map(Toml::Datetime)
+            .map_err(|e| format!("invalid datetime {:?}: {}", value, e)),
+        other => Err(format!("unknown type tag {:?}", other)),
+    }
+}
+
+fn parse_float(value: &str) -> Result<f64, std::num::ParseFloatError> {
+    match value {
+        "inf" | "+inf" => Ok(f64::INFINITY),
+        "-inf" => Ok(f64::NEG_INFINITY),
+        "nan" | "+nan" | "-nan" => Ok(f64::NAN),
+        _ => value.parse::<f64>(),
+    }
+}
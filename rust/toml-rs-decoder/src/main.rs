@@ -14,6 +14,12 @@
  *  - Implement "tagging" of JSON output in the format expected by the
  *    toml-test framework.
  *  - Exit with non-zero status if the TOML parser encounters an error.
+ *  - Add a "--diagnostics" mode that reports parse errors as
+ *    line:col-line:col spans instead of one opaque message.
+ *  - Add a "--mode plain|tagged|roundtrip" flag: "plain" restores
+ *    classic toml2json output (untyped JSON), "tagged" is the
+ *    toml-test tagged JSON (the default), and "roundtrip" decodes,
+ *    re-encodes and re-decodes the input to check that both agree.
  */
 
 use std::env;
@@ -25,30 +31,188 @@ use serde_json::Value as Json;
 use serde_json::json;
 use toml::Value as Toml;
 
+/// Which output this binary produces for a successfully parsed document.
+enum Mode {
+    /// Classic untyped `toml2json` output.
+    Plain,
+    /// Tagged JSON expected by the toml-test framework. The default.
+    Tagged,
+    /// Decode, re-encode and re-decode, and check that the result
+    /// agrees with the original decoded value.
+    Roundtrip,
+}
+
 fn main() {
-    let mut args = env::args();
+    let mut args: Vec<String> = env::args().skip(1).collect();
+
+    let diagnostics = match args.iter().position(|a| a == "--diagnostics") {
+        Some(pos) => {
+            args.remove(pos);
+            true
+        }
+        None => false,
+    };
+
+    let mode = match args.iter().position(|a| a == "--mode") {
+        Some(pos) => {
+            args.remove(pos);
+            if pos >= args.len() {
+                eprintln!("--mode requires an argument");
+                std::process::exit(2);
+            }
+            match args.remove(pos).as_str() {
+                "plain" => Mode::Plain,
+                "tagged" => Mode::Tagged,
+                "roundtrip" => Mode::Roundtrip,
+                other => {
+                    eprintln!(
+                        "unknown --mode {:?}, expected plain, tagged or roundtrip",
+                        other
+                    );
+                    std::process::exit(2);
+                }
+            }
+        }
+        None => Mode::Tagged,
+    };
+
+    let filename = args.into_iter().next();
     let mut input = String::new();
-    if args.len() > 1 {
-        let name = args.nth(1).unwrap();
-        File::open(&name)
-            .and_then(|mut f| f.read_to_string(&mut input))
-            .unwrap();
-    } else {
-        io::stdin().read_to_string(&mut input).unwrap();
+    match &filename {
+        Some(name) => {
+            File::open(name)
+                .and_then(|mut f| f.read_to_string(&mut input))
+                .unwrap();
+        }
+        None => {
+            io::stdin().read_to_string(&mut input).unwrap();
+        }
     }
 
     match input.parse() {
-        Ok(toml) => {
-            let json = convert(toml);
-            println!("{}", serde_json::to_string_pretty(&json).unwrap());
+        Ok(toml) => match mode {
+            Mode::Tagged => {
+                let json = convert(toml);
+                println!("{}", serde_json::to_string_pretty(&json).unwrap());
+            }
+            Mode::Plain => match convert_plain(toml) {
+                Ok(json) => {
+                    println!("{}", serde_json::to_string_pretty(&json).unwrap());
+                }
+                Err(error) => {
+                    println!("failed to convert to plain JSON: {}", error);
+                    std::process::exit(1);
+                }
+            },
+            Mode::Roundtrip => check_roundtrip(toml),
+        },
+        Err(error) => {
+            if diagnostics {
+                print_diagnostic(&input, filename.as_deref().unwrap_or("<stdin>"), &error);
+            } else {
+                println!("failed to parse TOML: {}", error);
+            }
+            std::process::exit(1);
         }
+    }
+}
+
+/// Re-serialize `toml` and parse the result back, to check that the
+/// decoder and encoder agree with each other. Exits non-zero if the
+/// re-serialized document fails to re-parse, or re-parses to a
+/// different value than the original.
+fn check_roundtrip(toml: Toml) {
+    let text = match toml::to_string(&toml) {
+        Ok(text) => text,
         Err(error) => {
-            println!("failed to parse TOML: {}", error);
+            println!("failed to re-serialize TOML: {}", error);
             std::process::exit(1);
         }
+    };
+
+    let reparsed: Toml = match text.parse() {
+        Ok(reparsed) => reparsed,
+        Err(error) => {
+            println!("failed to re-parse re-serialized TOML: {}", error);
+            std::process::exit(1);
+        }
+    };
+
+    if !toml_eq(&reparsed, &toml) {
+        println!("roundtrip mismatch: re-parsed value differs from the original");
+        std::process::exit(1);
+    }
+
+    println!("roundtrip OK");
+}
+
+/// Compare two `toml::Value`s for equality, treating `nan` as equal to
+/// `nan` (unlike the derived `PartialEq`, which compares floats with
+/// plain `==` and so never considers a document containing `nan` to
+/// round-trip cleanly).
+fn toml_eq(a: &Toml, b: &Toml) -> bool {
+    match (a, b) {
+        (Toml::Float(a), Toml::Float(b)) => (a.is_nan() && b.is_nan()) || a == b,
+        (Toml::Array(a), Toml::Array(b)) => {
+            a.len() == b.len() && a.iter().zip(b.iter()).all(|(x, y)| toml_eq(x, y))
+        }
+        (Toml::Table(a), Toml::Table(b)) => {
+            a.len() == b.len()
+                && a.iter()
+                    .all(|(k, v)| b.get(k).is_some_and(|w| toml_eq(v, w)))
+        }
+        _ => a == b,
     }
 }
 
+/// Print a parse error as a `file:loline:locol-hiline:hicol: message`
+/// diagnostic, with the offending source line and a caret underline,
+/// instead of the one-line `error.to_string()` form. This is meant for
+/// triaging which invalid-test expectations a parser change broke,
+/// rather than for machine consumption.
+fn print_diagnostic(input: &str, filename: &str, error: &toml::de::Error) {
+    let message = error.message();
+    let span = match error.span() {
+        Some(span) => span,
+        None => {
+            println!("{}: {}", filename, message);
+            return;
+        }
+    };
+
+    let (lo_line, lo_col) = offset_to_linecol(input, span.start);
+    let (hi_line, hi_col) = offset_to_linecol(input, span.end.max(span.start));
+    println!(
+        "{}:{}:{}-{}:{}: {}",
+        filename, lo_line, lo_col, hi_line, hi_col, message
+    );
+
+    if let Some(source_line) = input.lines().nth(lo_line - 1) {
+        println!("{}", source_line);
+        let caret_len = if hi_line == lo_line && hi_col > lo_col {
+            hi_col - lo_col
+        } else {
+            1
+        };
+        println!("{}{}", " ".repeat(lo_col - 1), "^".repeat(caret_len));
+    }
+}
+
+/// Convert a byte offset into `input` to a 1-based (line, column) pair.
+fn offset_to_linecol(input: &str, offset: usize) -> (usize, usize) {
+    let mut line = 1;
+    let mut col = 1;
+    for ch in input[..offset.min(input.len())].chars() {
+        if ch == '\n' {
+            line += 1;
+            col = 1;
+        } else {
+            col += 1;
+        }
+    }
+    (line, col)
+}
+
 fn convert(toml: Toml) -> Json {
     match toml {
         Toml::String(s) =>
@@ -99,3 +263,39 @@ fn convert(toml: Toml) -> Json {
           },
     }
 }
+
+/// Convert to ordinary untyped JSON, matching the classic `toml2json`
+/// behavior: strings as JSON strings, integers and floats as JSON
+/// numbers, booleans as JSON booleans, and datetimes as ISO strings.
+///
+/// JSON numbers cannot represent `nan` or `inf`/`-inf`, so a non-finite
+/// float is reported as an error rather than silently turned into
+/// `null`.
+fn convert_plain(toml: Toml) -> Result<Json, String> {
+    Ok(match toml {
+        Toml::String(s) => Json::String(s),
+        Toml::Integer(i) => json!(i),
+        Toml::Float(f) => {
+            if !f.is_finite() {
+                return Err(format!(
+                    "float {} has no representation in plain JSON",
+                    f
+                ));
+            }
+            json!(f)
+        }
+        Toml::Boolean(b) => Json::Bool(b),
+        Toml::Array(arr) => Json::Array(
+            arr.into_iter()
+                .map(convert_plain)
+                .collect::<Result<_, String>>()?,
+        ),
+        Toml::Table(table) => Json::Object(
+            table
+                .into_iter()
+                .map(|(k, v)| convert_plain(v).map(|json| (k, json)))
+                .collect::<Result<_, String>>()?,
+        ),
+        Toml::Datetime(dt) => Json::String(dt.to_string()),
+    })
+}
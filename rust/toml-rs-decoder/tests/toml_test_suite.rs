@@ -0,0 +1,250 @@
+/*
+ * toml_test_suite
+ *
+ * Integration test that drives the decoder (and, once wired up, the
+ * encoder) against the upstream toml-test corpus:
+ *   https://github.com/toml-lang/toml-test
+ *
+ * The corpus is cloned (or updated, if already present) into
+ * target/toml-test the first time this test runs. For every case under
+ * valid/ the decoder output is compared against the paired .json
+ * fixture using a semantic comparison: tables compare order-insensitive
+ * and typed leaves compare "type" plus a normalized "value" (so that,
+ * e.g., floats compare numerically rather than as strings). For every
+ * case under invalid/ the decoder is expected to exit with a non-zero
+ * status.
+ *
+ * A handful of cases are known to disagree with this decoder on
+ * datetime edge cases that are underspecified by the TOML spec; those
+ * are listed in BLACKLIST and skipped, following the precedent set by
+ * the boml test suite.
+ *
+ * This test clones the corpus from GitHub over the network, so it is
+ * `#[ignore]`d by default to keep `cargo test` working offline and in
+ * network-restricted sandboxes/CI; run it explicitly with
+ * `cargo test -- --ignored` in an environment that has network access.
+ */
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Test cases that are known to fail and are skipped rather than
+/// counted as failures.
+const BLACKLIST: &[&str] = &[
+    "valid/datetime/datetime",
+    "valid/datetime/local-date",
+    "valid/datetime/local-time",
+];
+
+#[test]
+#[ignore = "clones the toml-test corpus from GitHub; run with `--ignored` where network access is available"]
+fn run_toml_test_suite() {
+    let corpus = fetch_toml_test_corpus();
+
+    let mut valid_pass = 0;
+    let mut valid_fail = 0;
+    let mut valid_skip = 0;
+    for toml_path in collect_cases(&corpus.join("tests/valid"), "toml") {
+        let name = case_name(&corpus, &toml_path);
+        if BLACKLIST.contains(&name.as_str()) {
+            valid_skip += 1;
+            continue;
+        }
+        let json_path = toml_path.with_extension("json");
+        if check_valid_case(&toml_path, &json_path) {
+            valid_pass += 1;
+        } else {
+            eprintln!("FAIL (valid): {}", name);
+            valid_fail += 1;
+        }
+    }
+
+    let mut invalid_pass = 0;
+    let mut invalid_fail = 0;
+    let mut invalid_skip = 0;
+    for toml_path in collect_cases(&corpus.join("tests/invalid"), "toml") {
+        let name = case_name(&corpus, &toml_path);
+        if BLACKLIST.contains(&name.as_str()) {
+            invalid_skip += 1;
+            continue;
+        }
+        if check_invalid_case(&toml_path) {
+            invalid_pass += 1;
+        } else {
+            eprintln!("FAIL (invalid): {}", name);
+            invalid_fail += 1;
+        }
+    }
+
+    println!(
+        "valid: {} passed, {} failed, {} skipped",
+        valid_pass, valid_fail, valid_skip
+    );
+    println!(
+        "invalid: {} passed, {} failed, {} skipped",
+        invalid_pass, invalid_fail, invalid_skip
+    );
+
+    assert_eq!(valid_fail, 0, "{} valid case(s) failed", valid_fail);
+    assert_eq!(invalid_fail, 0, "{} invalid case(s) failed", invalid_fail);
+}
+
+/// Clone the toml-test corpus into target/toml-test, or pull the
+/// latest changes if it is already present.
+fn fetch_toml_test_corpus() -> PathBuf {
+    let dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("target")
+        .join("toml-test");
+
+    if dir.join(".git").is_dir() {
+        run_git(&["-C", dir.to_str().unwrap(), "pull", "--ff-only"]);
+    } else {
+        std::fs::create_dir_all(dir.parent().unwrap()).unwrap();
+        run_git(&[
+            "clone",
+            "https://github.com/toml-lang/toml-test",
+            dir.to_str().unwrap(),
+        ]);
+    }
+
+    dir
+}
+
+fn run_git(args: &[&str]) {
+    let status = Command::new("git")
+        .args(args)
+        .status()
+        .expect("failed to run git");
+    assert!(status.success(), "git {:?} failed", args);
+}
+
+/// Recursively collect every file with the given extension under `dir`.
+fn collect_cases(dir: &Path, extension: &str) -> Vec<PathBuf> {
+    let mut cases = Vec::new();
+    collect_cases_into(dir, extension, &mut cases);
+    cases.sort();
+    cases
+}
+
+fn collect_cases_into(dir: &Path, extension: &str, cases: &mut Vec<PathBuf>) {
+    let entries = match std::fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(_) => return,
+    };
+    for entry in entries {
+        let path = entry.unwrap().path();
+        if path.is_dir() {
+            collect_cases_into(&path, extension, cases);
+        } else if path.extension().and_then(|e| e.to_str()) == Some(extension) {
+            cases.push(path);
+        }
+    }
+}
+
+/// Derive the blacklist-comparable name of a case, e.g.
+/// "valid/datetime/local-time" from ".../tests/valid/datetime/local-time.toml".
+fn case_name(corpus: &Path, toml_path: &Path) -> String {
+    toml_path
+        .strip_prefix(corpus.join("tests"))
+        .unwrap()
+        .with_extension("")
+        .to_string_lossy()
+        .replace('\\', "/")
+}
+
+fn decoder_bin() -> &'static str {
+    env!("CARGO_BIN_EXE_toml-rs-decoder")
+}
+
+fn check_valid_case(toml_path: &Path, json_path: &Path) -> bool {
+    let output = Command::new(decoder_bin())
+        .arg(toml_path)
+        .output()
+        .expect("failed to run decoder");
+    if !output.status.success() {
+        return false;
+    }
+
+    let expected: serde_json::Value = match std::fs::read_to_string(json_path) {
+        Ok(text) => serde_json::from_str(&text).expect("fixture is not valid JSON"),
+        Err(_) => return false,
+    };
+    let actual: serde_json::Value = match serde_json::from_slice(&output.stdout) {
+        Ok(value) => value,
+        Err(_) => return false,
+    };
+
+    semantically_equal(&expected, &actual)
+}
+
+fn check_invalid_case(toml_path: &Path) -> bool {
+    let status = Command::new(decoder_bin())
+        .arg(toml_path)
+        .status()
+        .expect("failed to run decoder");
+    !status.success()
+}
+
+/// Compare two tagged-JSON values, ignoring table key order and
+/// normalizing leaf values by type so that e.g. "1e0" and "1.0" compare
+/// equal as floats.
+fn semantically_equal(a: &serde_json::Value, b: &serde_json::Value) -> bool {
+    match (a, b) {
+        (serde_json::Value::Object(a), serde_json::Value::Object(b)) => {
+            if is_tagged_value(a) && is_tagged_value(b) {
+                return tagged_values_equal(a, b);
+            }
+            let a: BTreeMap<_, _> = a.iter().collect();
+            let b: BTreeMap<_, _> = b.iter().collect();
+            a.len() == b.len()
+                && a.iter()
+                    .all(|(k, v)| b.get(k).is_some_and(|w| semantically_equal(v, w)))
+        }
+        (serde_json::Value::Array(a), serde_json::Value::Array(b)) => {
+            a.len() == b.len()
+                && a.iter()
+                    .zip(b.iter())
+                    .all(|(x, y)| semantically_equal(x, y))
+        }
+        _ => a == b,
+    }
+}
+
+fn is_tagged_value(obj: &serde_json::Map<String, serde_json::Value>) -> bool {
+    obj.len() == 2 && obj.contains_key("type") && obj.contains_key("value")
+}
+
+fn tagged_values_equal(
+    a: &serde_json::Map<String, serde_json::Value>,
+    b: &serde_json::Map<String, serde_json::Value>,
+) -> bool {
+    let (Some(type_a), Some(type_b)) = (a["type"].as_str(), b["type"].as_str()) else {
+        return false;
+    };
+    if type_a != type_b {
+        return false;
+    }
+    let (Some(value_a), Some(value_b)) = (a["value"].as_str(), b["value"].as_str()) else {
+        return false;
+    };
+
+    match type_a {
+        "float" => {
+            let (a, b) = (parse_float(value_a), parse_float(value_b));
+            (a.is_nan() && b.is_nan()) || a == b
+        }
+        "integer" => value_a.parse::<i64>().ok() == value_b.parse::<i64>().ok(),
+        "bool" => value_a.parse::<bool>().ok() == value_b.parse::<bool>().ok(),
+        _ => value_a == value_b,
+    }
+}
+
+fn parse_float(value: &str) -> f64 {
+    match value {
+        "inf" | "+inf" => f64::INFINITY,
+        "-inf" => f64::NEG_INFINITY,
+        "nan" | "+nan" | "-nan" => f64::NAN,
+        other => other.parse().unwrap_or(f64::NAN),
+    }
+}